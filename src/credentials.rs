@@ -2,6 +2,8 @@ use thiserror::Error;
 
 use std::{io, path::Path, path::PathBuf};
 
+use crate::encrypted::EncryptedValue;
+
 type Result<T> = std::result::Result<T, CredentialsError>;
 
 /// Error enum for crate functions. Used for all `Result` returns as the error
@@ -27,11 +29,36 @@ pub enum CredentialsError {
     /// Set when it's impossible to find your base directory
     #[error("No base directory on this OS. Unable to find default b2 accounts")]
     NoBaseDirs,
+
+    /// Set when a credentials source exists but holds an empty credential
+    #[error("Credential is empty")]
+    EmptyCreds,
+
+    /// Set when an encrypted application_key cannot be decrypted, either
+    /// because no passphrase was supplied or because the supplied passphrase
+    /// is wrong
+    #[error("Failed to decrypt application_key")]
+    Decrypt,
+
+    /// Set when the credentials file is group- or world-readable/writable
+    #[error("Credentials file has insecure permissions: {mode:o}")]
+    InsecurePermissions {
+        /// The offending Unix file mode
+        mode: u32,
+    },
 }
 
 const KEY_ENV_VAR_NAME: &str = "B2_APPLICATION_KEY";
 const KEY_ID_ENV_VAR_NAME: &str = "B2_APPLICATION_KEY_ID";
 
+const SYSTEMD_CREDENTIALS_DIR_ENV_VAR_NAME: &str = "CREDENTIALS_DIRECTORY";
+const SYSTEMD_KEY_CREDENTIAL_NAME: &str = "b2_application_key";
+const SYSTEMD_KEY_ID_CREDENTIAL_NAME: &str = "b2_application_key_id";
+
+const PROFILE_FILE_ENV_VAR_NAME: &str = "B2_CREDENTIALS_FILE";
+const PROFILE_ENV_VAR_NAME: &str = "B2_PROFILE";
+const DEFAULT_PROFILE_NAME: &str = "default";
+
 /// Holds the application key id and application key which make up your
 /// credentials
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -43,6 +70,20 @@ pub struct Credentials {
     pub application_key: String,
 }
 
+/// Options controlling how [`Credentials::from_file_with_opts`] reads the
+/// credentials database.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FromFileOpts<'a> {
+    /// Decrypts an encrypted `application_key` with this passphrase. Ignored
+    /// for accounts whose `application_key` is stored in plain text.
+    pub passphrase: Option<&'a str>,
+
+    /// Skips the check that otherwise rejects a credentials file that is
+    /// group- or world-readable/writable. Only set this if you knowingly
+    /// share the file. No-op on non-Unix platforms.
+    pub allow_insecure_permissions: bool,
+}
+
 impl Credentials {
     /// Returns the default credentials for b2. This function will search for b2
     /// credentials in the following order:
@@ -50,10 +91,19 @@ impl Credentials {
     /// 1. In the B2_APPLICATION_KEY and B2_APPLICATION_KEY_ID environmentals
     ///    variables
     ///
-    /// 2. In the sqlite database pointed to by the environmental variable
+    /// 2. In the systemd credentials directory pointed to by the
+    ///    CREDENTIALS_DIRECTORY environmental variable, as set up by
+    ///    `LoadCredential=`/`ImportCredential=`. Any error from this step,
+    ///    such as a missing or empty credential file, is not returned; the
+    ///    search moves on to the next step
+    ///
+    /// 3. In the sqlite database pointed to by the environmental variable
     ///    B2_ACCOUNT_INFO
     ///
-    /// 3. In the default sqlite database ~/.b2_account_info
+    /// 4. In the default sqlite database returned by [`default_creds_file`]:
+    ///    ~/.b2_account_info, or the `account_info` file under the platform
+    ///    config directory (e.g. ~/.config/b2/account_info on Linux) if only
+    ///    that one exists
     ///
     /// If any of those searches run into an unexpected error, that error is
     /// returned. Otherwise `CredentialsError::NoCreds` is returned.
@@ -67,7 +117,10 @@ impl Credentials {
     pub fn default() -> Result<Self> {
         match Self::from_env() {
             Ok(res) => Ok(res),
-            Err(_) => Self::from_file(None, None),
+            Err(_) => match Self::from_systemd_credentials() {
+                Ok(res) => Ok(res),
+                Err(_) => Self::from_file(None, None),
+            },
         }
     }
 
@@ -104,6 +157,40 @@ impl Credentials {
         })
     }
 
+    /// Attempts to extract b2 credentials from files handed to the process by
+    /// systemd's `LoadCredential=`/`ImportCredential=` mechanism.
+    ///
+    /// The directory containing the credential files is read from the
+    /// `CREDENTIALS_DIRECTORY` environmental variable. The application key is
+    /// read from a file named `b2_application_key` inside that directory, and
+    /// the key id from a file named `b2_application_key_id`. Trailing newlines
+    /// are trimmed from both files.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let creds = b2creds::Credentials::from_systemd_credentials().unwrap();
+    /// println!("Key ID: {} Key: {}", creds.application_key_id, creds.application_key);
+    /// ```
+    pub fn from_systemd_credentials() -> Result<Self> {
+        let dir = match std::env::var(SYSTEMD_CREDENTIALS_DIR_ENV_VAR_NAME) {
+            Ok(value) => value,
+            Err(e) => match e {
+                std::env::VarError::NotPresent => return Err(CredentialsError::NoCreds),
+                _ => return Err(CredentialsError::Env(e)),
+            },
+        };
+        let dir = PathBuf::from(dir);
+
+        let key = read_systemd_credential(&dir.join(SYSTEMD_KEY_CREDENTIAL_NAME))?;
+        let key_id = read_systemd_credential(&dir.join(SYSTEMD_KEY_ID_CREDENTIAL_NAME))?;
+
+        Ok(Self {
+            application_key_id: key_id,
+            application_key: key,
+        })
+    }
+
     /// Attempts to extract b2 credentials from a b2 account info file. The path
     /// to this file maybe specified via the `db_path` argument. If that argument
     /// is None, the path set in the env variable B2_ACCOUNT_INFO is used, and if
@@ -130,60 +217,464 @@ impl Credentials {
     /// println!("Key ID: {} Key: {}", creds.application_key_id, creds.application_key);
     /// ```
     pub fn from_file(db_path: Option<&Path>, account_id: Option<&str>) -> Result<Self> {
-        let db_path = if let Some(path) = db_path {
-            path.to_path_buf()
-        } else if let Ok(env_path) = std::env::var("B2_ACCOUNT_INFO") {
-            PathBuf::from(env_path)
-        } else {
-            default_creds_file()?
-        };
-        Self::from_file_internal(&db_path, account_id)
+        Self::from_file_with_opts(db_path, account_id, FromFileOpts::default())
     }
 
-    fn from_file_internal(db_path: &std::path::Path, account_id: Option<&str>) -> Result<Self> {
-        if !db_path.exists() {
-            return Err(CredentialsError::NoCreds);
-        }
-
-        let conn = rusqlite::Connection::open_with_flags(
+    /// Same as [`Credentials::from_file`], but decrypts the `application_key`
+    /// with `passphrase` if it was stored encrypted via
+    /// [`Credentials::save`]. `passphrase` is ignored for accounts whose
+    /// `application_key` is stored in plain text.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let creds =
+    ///     b2creds::Credentials::from_file_with_passphrase(None, None, Some("hunter2")).unwrap();
+    /// println!("Key ID: {} Key: {}", creds.application_key_id, creds.application_key);
+    /// ```
+    pub fn from_file_with_passphrase(
+        db_path: Option<&Path>,
+        account_id: Option<&str>,
+        passphrase: Option<&str>,
+    ) -> Result<Self> {
+        Self::from_file_with_opts(
             db_path,
-            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
-        )?;
+            account_id,
+            FromFileOpts {
+                passphrase,
+                ..FromFileOpts::default()
+            },
+        )
+    }
+
+    /// Same as [`Credentials::from_file`], but with full control over how the
+    /// file is read via `opts`. See [`FromFileOpts`] for the available
+    /// knobs, including opting out of the permissions check that otherwise
+    /// rejects a group- or world-readable/writable credentials file.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let opts = b2creds::FromFileOpts {
+    ///     allow_insecure_permissions: true,
+    ///     ..Default::default()
+    /// };
+    /// let creds = b2creds::Credentials::from_file_with_opts(None, None, opts).unwrap();
+    /// println!("Key ID: {} Key: {}", creds.application_key_id, creds.application_key);
+    /// ```
+    pub fn from_file_with_opts(
+        db_path: Option<&Path>,
+        account_id: Option<&str>,
+        opts: FromFileOpts,
+    ) -> Result<Self> {
+        let db_path = resolve_db_path(db_path)?;
+        Self::from_file_internal(&db_path, account_id, opts)
+    }
+
+    fn from_file_internal(
+        db_path: &std::path::Path,
+        account_id: Option<&str>,
+        opts: FromFileOpts,
+    ) -> Result<Self> {
+        let conn = open_db(db_path, opts.allow_insecure_permissions)?;
 
         let mut query = String::from(
             "SELECT account_id, application_key, account_id_or_app_key_id FROM account",
         );
-        if let Some(account_id) = account_id {
-            query = format!("{} WHERE account_id = \"{}\"", query, account_id);
+        if account_id.is_some() {
+            query.push_str(" WHERE account_id = ?1");
         }
 
         let mut stmt = conn.prepare(&query)?;
 
-        let creds_iter = stmt.query_map(rusqlite::NO_PARAMS, |row| {
+        let rows_iter = match account_id {
+            Some(account_id) => stmt.query_map(rusqlite::params![account_id], AccountRow::from_row)?,
+            None => stmt.query_map(rusqlite::NO_PARAMS, AccountRow::from_row)?,
+        };
+        let mut rows_iter = rows_iter.filter_map(std::result::Result::ok);
+
+        if let Some(row) = rows_iter.next() {
             Ok(Credentials {
-                application_key_id: row.get(2).unwrap(),
-                application_key: row.get(1).unwrap(),
+                application_key_id: row.application_key_id,
+                application_key: row.application_key.reveal(opts.passphrase)?,
             })
-        })?;
+        } else {
+            Err(CredentialsError::NoCreds)
+        }
+    }
+
+    /// Returns every account stored in the sqlite database at `db_path`,
+    /// paired with its `account_id`, so callers can enumerate the available
+    /// B2 accounts and present a chooser rather than guessing an
+    /// `account_id` to pass into [`Credentials::from_file`].
+    ///
+    /// `passphrase` is used to decrypt any accounts stored encrypted; a
+    /// plaintext account is unaffected by it. An account whose
+    /// `application_key` can't be revealed with the given `passphrase` is
+    /// skipped rather than failing the whole call, so a single encrypted
+    /// account doesn't hide every other account from the caller.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// for (account_id, creds) in b2creds::Credentials::list_accounts(None, None).unwrap() {
+    ///     println!("{}: {}", account_id, creds.application_key_id);
+    /// }
+    /// ```
+    pub fn list_accounts(
+        db_path: Option<&Path>,
+        passphrase: Option<&str>,
+    ) -> Result<Vec<(String, Credentials)>> {
+        let db_path = resolve_db_path(db_path)?;
+        let conn = open_db(&db_path, false)?;
+
+        let mut stmt = conn.prepare(
+            "SELECT account_id, application_key, account_id_or_app_key_id FROM account",
+        )?;
+        let rows_iter = stmt.query_map(rusqlite::NO_PARAMS, AccountRow::from_row)?;
+
+        let mut accounts = Vec::new();
+        for row in rows_iter {
+            let row = row?;
+            let application_key = match row.application_key.reveal(passphrase) {
+                Ok(application_key) => application_key,
+                Err(CredentialsError::Decrypt) => continue,
+                Err(err) => return Err(err),
+            };
+            accounts.push((
+                row.account_id,
+                Credentials {
+                    application_key_id: row.application_key_id,
+                    application_key,
+                },
+            ));
+        }
 
-        let mut creds_iter = creds_iter.filter_map(std::result::Result::ok);
+        Ok(accounts)
+    }
 
-        if let Some(cred) = creds_iter.next() {
-            Ok(cred)
+    /// Persists these credentials to the sqlite database at `db_path` under
+    /// `account_id`, creating the `account` table if it does not already
+    /// exist and overwriting any existing row for that `account_id`.
+    ///
+    /// If `passphrase` is `Some`, the `application_key` is encrypted at rest
+    /// using a key derived from the passphrase; otherwise it is stored in
+    /// plain text, matching the layout the B2 CLI itself uses.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    ///
+    /// let creds = b2creds::Credentials::from_env().unwrap();
+    /// creds
+    ///     .save(Path::new("/tmp/b2_account_info"), "my-account", Some("hunter2"))
+    ///     .unwrap();
+    /// ```
+    pub fn save(&self, db_path: &Path, account_id: &str, passphrase: Option<&str>) -> Result<()> {
+        let conn = rusqlite::Connection::open(db_path)?;
+        secure_file_permissions(db_path)?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS account (
+                account_id TEXT NOT NULL,
+                application_key BLOB NOT NULL,
+                account_id_or_app_key_id TEXT
+            )",
+            rusqlite::params![],
+        )?;
+
+        let stored_key = match passphrase {
+            Some(passphrase) => EncryptedValue::encrypt(&self.application_key, passphrase)?,
+            None => EncryptedValue::plain(&self.application_key),
+        };
+
+        conn.execute(
+            "DELETE FROM account WHERE account_id = ?1",
+            rusqlite::params![account_id],
+        )?;
+        conn.execute(
+            "INSERT INTO account (account_id, application_key, account_id_or_app_key_id) VALUES (?1, ?2, ?3)",
+            rusqlite::params![account_id, stored_key, self.application_key_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Attempts to extract b2 credentials from an AWS-style shared
+    /// credentials INI file. The path to this file may be specified via the
+    /// `path` argument. If that argument is `None`, the path set in the env
+    /// variable `B2_CREDENTIALS_FILE` is used, and if that environmental
+    /// variable is not set, the path searched defaults to `~/.b2/credentials`.
+    ///
+    /// The file may have multiple named profiles, each started by a
+    /// `[profile-name]` section header and containing `application_key_id`
+    /// and `application_key` keys. By default the `default` profile is
+    /// chosen, but users may specify a different one via the `profile`
+    /// argument or, if that is `None`, the `B2_PROFILE` env variable.
+    ///
+    /// Comments starting with `#` or `;`, surrounding whitespace, and quoted
+    /// values are all tolerated.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The (optional) path to the credentials file. If not set, it
+    ///            defaults to the B2_CREDENTIALS_FILE env variable and then
+    ///            ~/.b2/credentials.
+    ///
+    /// * `profile` - The name of the profile to read. If not set, it defaults
+    ///               to the B2_PROFILE env variable and then "default".
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let creds = b2creds::Credentials::from_profile(None, None).unwrap();
+    /// println!("Key ID: {} Key: {}", creds.application_key_id, creds.application_key);
+    /// ```
+    pub fn from_profile(path: Option<&Path>, profile: Option<&str>) -> Result<Self> {
+        let path = if let Some(path) = path {
+            path.to_path_buf()
+        } else if let Ok(env_path) = std::env::var(PROFILE_FILE_ENV_VAR_NAME) {
+            PathBuf::from(env_path)
         } else {
-            Err(CredentialsError::NoCreds)
+            default_profile_file()?
+        };
+
+        let profile = if let Some(profile) = profile {
+            profile.to_string()
+        } else if let Ok(env_profile) = std::env::var(PROFILE_ENV_VAR_NAME) {
+            env_profile
+        } else {
+            DEFAULT_PROFILE_NAME.to_string()
+        };
+
+        if !path.exists() {
+            return Err(CredentialsError::NoCreds);
         }
+
+        let contents = std::fs::read_to_string(&path)?;
+        let section = parse_ini_sections(&contents)
+            .into_iter()
+            .find(|(name, _)| name == &profile)
+            .map(|(_, values)| values)
+            .ok_or(CredentialsError::NoCreds)?;
+
+        let key = section
+            .get("application_key")
+            .ok_or(CredentialsError::NoCreds)?
+            .clone();
+        let key_id = section
+            .get("application_key_id")
+            .ok_or(CredentialsError::NoCreds)?
+            .clone();
+
+        Ok(Self {
+            application_key_id: key_id,
+            application_key: key,
+        })
     }
 }
 
-/// Returns the default credentials file path.
+fn resolve_db_path(db_path: Option<&Path>) -> Result<PathBuf> {
+    if let Some(path) = db_path {
+        Ok(path.to_path_buf())
+    } else if let Ok(env_path) = std::env::var("B2_ACCOUNT_INFO") {
+        Ok(PathBuf::from(env_path))
+    } else {
+        default_creds_file()
+    }
+}
+
+fn open_db(db_path: &Path, allow_insecure_permissions: bool) -> Result<rusqlite::Connection> {
+    if !db_path.exists() {
+        return Err(CredentialsError::NoCreds);
+    }
+
+    if !allow_insecure_permissions {
+        check_file_permissions(db_path)?;
+    }
+
+    Ok(rusqlite::Connection::open_with_flags(
+        db_path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )?)
+}
+
+/// Maps a single row of the `account` table to its columns. Centralizing the
+/// column mapping here keeps [`Credentials::from_file`] and
+/// [`Credentials::list_accounts`] in sync as the schema evolves.
+struct AccountRow {
+    account_id: String,
+    application_key_id: String,
+    application_key: EncryptedValue,
+}
+
+impl AccountRow {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            account_id: row.get(0)?,
+            application_key: row.get(1)?,
+            application_key_id: row.get(2)?,
+        })
+    }
+}
+
+#[cfg(unix)]
+fn check_file_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        return Err(CredentialsError::InsecurePermissions { mode: mode & 0o777 });
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_file_permissions(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Restricts `path` to owner-only access, so a database written by
+/// [`Credentials::save`] passes [`check_file_permissions`] on the very next
+/// read instead of inheriting whatever the process umask left behind.
+#[cfg(unix)]
+fn secure_file_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn secure_file_permissions(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+fn read_systemd_credential(path: &Path) -> Result<String> {
+    let contents = std::fs::read_to_string(path)?;
+    let trimmed = contents.trim_end_matches(['\n', '\r']);
+    if trimmed.is_empty() {
+        return Err(CredentialsError::EmptyCreds);
+    }
+    Ok(trimmed.to_string())
+}
+
+fn default_profile_file() -> Result<PathBuf> {
+    let home_dir = directories::BaseDirs::new().ok_or(CredentialsError::NoBaseDirs)?;
+    Ok(PathBuf::from(home_dir.home_dir())
+        .join(".b2")
+        .join("credentials"))
+}
+
+/// Parses the contents of an INI-style shared credentials file into an
+/// ordered list of `(section name, key/value pairs)`.
+fn parse_ini_sections(contents: &str) -> Vec<(String, std::collections::HashMap<String, String>)> {
+    let mut sections: Vec<(String, std::collections::HashMap<String, String>)> = Vec::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        let line = strip_inline_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with('[') && line.ends_with(']') {
+            let name = line[1..line.len() - 1].trim().to_string();
+            sections.push((name, std::collections::HashMap::new()));
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once('=') {
+            if let Some((_, values)) = sections.last_mut() {
+                values.insert(key.trim().to_string(), unquote_ini_value(value.trim()));
+            }
+        }
+    }
+
+    sections
+}
+
+/// Truncates `line` at the first `#` or `;` that falls outside of a quoted
+/// value, so a trailing comment after a `key = value` pair is not treated as
+/// part of the value.
+fn strip_inline_comment(line: &str) -> &str {
+    let mut in_quote = None;
+    for (idx, ch) in line.char_indices() {
+        match in_quote {
+            Some(quote) if ch == quote => in_quote = None,
+            Some(_) => {}
+            None => match ch {
+                '"' | '\'' => in_quote = Some(ch),
+                '#' | ';' => return &line[..idx],
+                _ => {}
+            },
+        }
+    }
+    line
+}
+
+fn unquote_ini_value(value: &str) -> String {
+    let bytes = value.as_bytes();
+    if bytes.len() >= 2 {
+        let (first, last) = (bytes[0], bytes[bytes.len() - 1]);
+        if (first == b'"' && last == b'"') || (first == b'\'' && last == b'\'') {
+            return value[1..value.len() - 1].to_string();
+        }
+    }
+    value.to_string()
+}
+
+fn legacy_creds_file() -> Result<PathBuf> {
+    let home_dir = directories::BaseDirs::new().ok_or(CredentialsError::NoBaseDirs)?;
+    Ok(PathBuf::from(home_dir.home_dir()).join(".b2_account_info"))
+}
+
+/// Returns, in search order, every location [`default_creds_file`] considers
+/// when looking for a B2 credentials database:
+///
+/// 1. The path set in the `B2_ACCOUNT_INFO` environmental variable, if any
+///
+/// 2. The legacy `~/.b2_account_info` file used by older B2 CLI releases
+///
+/// 3. The `account_info` sqlite file under the platform config directory
+///    (e.g. `~/.config/b2/account_info` on Linux) used by newer releases
+///
+/// Exposing this list lets callers diagnose which file was actually picked
+/// up by [`default_creds_file`].
+pub fn candidate_creds_files() -> Result<Vec<PathBuf>> {
+    let mut candidates = Vec::new();
+
+    if let Ok(env_path) = std::env::var("B2_ACCOUNT_INFO") {
+        candidates.push(PathBuf::from(env_path));
+    }
+
+    candidates.push(legacy_creds_file()?);
+
+    if let Some(project_dirs) = directories::ProjectDirs::from("", "", "b2") {
+        candidates.push(project_dirs.config_dir().join("account_info"));
+    }
+
+    Ok(candidates)
+}
+
+/// Returns the default credentials file path: the first of
+/// [`candidate_creds_files`] that exists, or the legacy `~/.b2_account_info`
+/// location if none of them do.
 /// ```
 /// let cred_path = b2creds::default_creds_file().unwrap();
 /// println!("B2 Creds Path: {}", cred_path.display());
 /// ```
 pub fn default_creds_file() -> Result<PathBuf> {
-    let home_dir = directories::BaseDirs::new().ok_or(CredentialsError::NoBaseDirs)?;
-    Ok(PathBuf::from(home_dir.home_dir()).join(".b2_account_info"))
+    let candidates = candidate_creds_files()?;
+    match candidates.into_iter().find(|path| path.exists()) {
+        Some(path) => Ok(path),
+        None => legacy_creds_file(),
+    }
 }
 
 #[cfg(test)]
@@ -199,6 +690,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn candidate_creds_files_includes_legacy_and_xdg_paths() -> Result<()> {
+        std::env::remove_var("B2_ACCOUNT_INFO");
+
+        let candidates = candidate_creds_files()?;
+        let home = std::env::var("HOME")?;
+        let legacy_path = PathBuf::from(home).join(".b2_account_info");
+
+        assert!(candidates.contains(&legacy_path));
+        assert!(candidates
+            .iter()
+            .any(|path| path.ends_with("b2/account_info") || path.ends_with("b2\\account_info")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn candidate_creds_files_prefers_env_override_first() -> Result<()> {
+        let bad_path = PathBuf::from("asgasgasldghuaskdjgkkajsjuugasdgasg");
+        std::env::set_var("B2_ACCOUNT_INFO", &bad_path);
+
+        let candidates = candidate_creds_files()?;
+        std::env::remove_var("B2_ACCOUNT_INFO");
+
+        assert_eq!(candidates[0], bad_path);
+
+        Ok(())
+    }
+
     #[test]
     fn from_env_fails_with_no_key_or_key_id() {
         clear_env();
@@ -242,6 +762,51 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn from_systemd_credentials_fails_with_no_dir() {
+        clear_env();
+        std::env::remove_var(SYSTEMD_CREDENTIALS_DIR_ENV_VAR_NAME);
+        assert!(matches!(
+            Credentials::from_systemd_credentials().unwrap_err(),
+            CredentialsError::NoCreds
+        ));
+    }
+
+    #[test]
+    fn from_systemd_credentials_fails_with_empty_key() -> Result<()> {
+        clear_env();
+
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join(SYSTEMD_KEY_CREDENTIAL_NAME), "")?;
+        std::fs::write(dir.path().join(SYSTEMD_KEY_ID_CREDENTIAL_NAME), "keyid\n")?;
+        std::env::set_var(SYSTEMD_CREDENTIALS_DIR_ENV_VAR_NAME, dir.path());
+
+        assert!(matches!(
+            Credentials::from_systemd_credentials().unwrap_err(),
+            CredentialsError::EmptyCreds
+        ));
+
+        std::env::remove_var(SYSTEMD_CREDENTIALS_DIR_ENV_VAR_NAME);
+        Ok(())
+    }
+
+    #[test]
+    fn from_systemd_credentials_works() -> Result<()> {
+        clear_env();
+
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join(SYSTEMD_KEY_CREDENTIAL_NAME), "key\n")?;
+        std::fs::write(dir.path().join(SYSTEMD_KEY_ID_CREDENTIAL_NAME), "keyid\n")?;
+        std::env::set_var(SYSTEMD_CREDENTIALS_DIR_ENV_VAR_NAME, dir.path());
+
+        let creds = Credentials::from_systemd_credentials()?;
+        assert_eq!(creds.application_key, "key");
+        assert_eq!(creds.application_key_id, "keyid");
+
+        std::env::remove_var(SYSTEMD_CREDENTIALS_DIR_ENV_VAR_NAME);
+        Ok(())
+    }
+
     #[test]
     fn non_existant_path_fails() {
         clear_env();
@@ -328,6 +893,132 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn insecure_permissions_are_rejected() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        clear_env();
+
+        let file = tempfile::NamedTempFile::new()?;
+        let creds = Credentials {
+            application_key_id: "key_id".to_string(),
+            application_key: "key".to_string(),
+        };
+        creds.save(file.path(), "123", None)?;
+
+        std::fs::set_permissions(file.path(), std::fs::Permissions::from_mode(0o644))?;
+
+        let result = Credentials::from_file(Some(file.path()), None);
+        assert!(matches!(
+            result.unwrap_err(),
+            CredentialsError::InsecurePermissions { mode: 0o644 }
+        ));
+
+        let opts = FromFileOpts {
+            allow_insecure_permissions: true,
+            ..Default::default()
+        };
+        let loaded = Credentials::from_file_with_opts(Some(file.path()), None, opts)?;
+        assert_eq!(loaded, creds);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn save_creates_file_with_secure_permissions() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        clear_env();
+
+        let dir = tempfile::tempdir()?;
+        let db_path = dir.path().join("fresh_account_info");
+        let creds = Credentials {
+            application_key_id: "key_id".to_string(),
+            application_key: "key".to_string(),
+        };
+        creds.save(&db_path, "123", None)?;
+
+        let mode = std::fs::metadata(&db_path)?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let loaded = Credentials::from_file(Some(&db_path), None)?;
+        assert_eq!(loaded, creds);
+
+        Ok(())
+    }
+
+    #[test]
+    fn save_and_reload_plaintext_round_trips() -> Result<()> {
+        clear_env();
+
+        let file = tempfile::NamedTempFile::new()?;
+        let creds = Credentials {
+            application_key_id: "key_id".to_string(),
+            application_key: "key".to_string(),
+        };
+        creds.save(file.path(), "123", None)?;
+
+        let loaded = Credentials::from_file(Some(file.path()), None)?;
+        assert_eq!(loaded, creds);
+
+        Ok(())
+    }
+
+    #[test]
+    fn save_and_reload_encrypted_round_trips() -> Result<()> {
+        clear_env();
+
+        let file = tempfile::NamedTempFile::new()?;
+        let creds = Credentials {
+            application_key_id: "key_id".to_string(),
+            application_key: "key".to_string(),
+        };
+        creds.save(file.path(), "123", Some("hunter2"))?;
+
+        let loaded =
+            Credentials::from_file_with_passphrase(Some(file.path()), None, Some("hunter2"))?;
+        assert_eq!(loaded, creds);
+
+        let wrong_passphrase =
+            Credentials::from_file_with_passphrase(Some(file.path()), None, Some("wrong"));
+        assert!(matches!(
+            wrong_passphrase.unwrap_err(),
+            CredentialsError::Decrypt
+        ));
+
+        let no_passphrase = Credentials::from_file(Some(file.path()), None);
+        assert!(matches!(
+            no_passphrase.unwrap_err(),
+            CredentialsError::Decrypt
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn save_overwrites_existing_account() -> Result<()> {
+        clear_env();
+
+        let file = tempfile::NamedTempFile::new()?;
+        let first = Credentials {
+            application_key_id: "key_id".to_string(),
+            application_key: "key".to_string(),
+        };
+        let second = Credentials {
+            application_key_id: "new_key_id".to_string(),
+            application_key: "new_key".to_string(),
+        };
+        first.save(file.path(), "123", None)?;
+        second.save(file.path(), "123", None)?;
+
+        let loaded = Credentials::from_file(Some(file.path()), Some("123"))?;
+        assert_eq!(loaded, second);
+
+        Ok(())
+    }
+
     #[test]
     fn empty_table_fails() -> Result<()> {
         clear_env();
@@ -353,6 +1044,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn account_id_with_quote_is_matched_literally() -> Result<()> {
+        clear_env();
+
+        let file = tempfile::NamedTempFile::new()?;
+        let creds = Credentials {
+            application_key_id: "key_id".to_string(),
+            application_key: "key".to_string(),
+        };
+        creds.save(file.path(), "123\" OR 1=1 --", None)?;
+
+        let loaded = Credentials::from_file(Some(file.path()), Some("123\" OR 1=1 --"))?;
+        assert_eq!(loaded, creds);
+
+        let result = Credentials::from_file(Some(file.path()), Some("123"));
+        assert!(matches!(result.unwrap_err(), CredentialsError::NoCreds));
+
+        Ok(())
+    }
+
     #[test]
     fn account_id_works() -> Result<()> {
         clear_env();
@@ -407,8 +1118,183 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn list_accounts_returns_every_account() -> Result<()> {
+        clear_env();
+
+        let file = tempfile::NamedTempFile::new()?;
+        let first = Credentials {
+            application_key_id: "key_id_1".to_string(),
+            application_key: "key_1".to_string(),
+        };
+        let second = Credentials {
+            application_key_id: "key_id_2".to_string(),
+            application_key: "key_2".to_string(),
+        };
+        first.save(file.path(), "123", None)?;
+        second.save(file.path(), "456", None)?;
+
+        let mut accounts = Credentials::list_accounts(Some(file.path()), None)?;
+        accounts.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        assert_eq!(
+            accounts,
+            vec![("123".to_string(), first), ("456".to_string(), second)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn list_accounts_skips_accounts_it_cannot_decrypt() -> Result<()> {
+        clear_env();
+
+        let file = tempfile::NamedTempFile::new()?;
+        let plain = Credentials {
+            application_key_id: "key_id_1".to_string(),
+            application_key: "key_1".to_string(),
+        };
+        let encrypted = Credentials {
+            application_key_id: "key_id_2".to_string(),
+            application_key: "key_2".to_string(),
+        };
+        plain.save(file.path(), "123", None)?;
+        encrypted.save(file.path(), "456", Some("hunter2"))?;
+
+        let accounts = Credentials::list_accounts(Some(file.path()), None)?;
+        assert_eq!(accounts, vec![("123".to_string(), plain)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn list_accounts_empty_table_returns_empty_vec() -> Result<()> {
+        clear_env();
+
+        let file = tempfile::NamedTempFile::new()?;
+
+        let conn = rusqlite::Connection::open(file.path())?;
+        conn.execute(
+            "CREATE TABLE account (
+                    account_id TEXT NOT NULL,
+                    application_key TEXT NOT NULL,
+                    account_id_or_app_key_id TEXT
+                    )",
+            rusqlite::params![],
+        )?;
+        conn.flush_prepared_statement_cache();
+        conn.close().unwrap();
+
+        let accounts = Credentials::list_accounts(Some(file.path()), None)?;
+        assert!(accounts.is_empty());
+
+        Ok(())
+    }
+
     fn clear_env() {
         std::env::remove_var(KEY_ID_ENV_VAR_NAME);
         std::env::remove_var(KEY_ENV_VAR_NAME);
     }
+
+    fn clear_profile_env() {
+        std::env::remove_var(PROFILE_FILE_ENV_VAR_NAME);
+        std::env::remove_var(PROFILE_ENV_VAR_NAME);
+    }
+
+    #[test]
+    fn from_profile_fails_with_missing_file() {
+        clear_profile_env();
+
+        let bad_path = PathBuf::from("asgasgasldghuaskdjgkkajsjuugasdgasg");
+        let creds = Credentials::from_profile(Some(&bad_path), None);
+        assert!(matches!(creds.unwrap_err(), CredentialsError::NoCreds));
+    }
+
+    #[test]
+    fn from_profile_fails_with_missing_section() -> Result<()> {
+        clear_profile_env();
+
+        let file = tempfile::NamedTempFile::new()?;
+        std::fs::write(
+            file.path(),
+            "[other]\napplication_key_id = id\napplication_key = key\n",
+        )?;
+
+        let creds = Credentials::from_profile(Some(file.path()), None);
+        assert!(matches!(creds.unwrap_err(), CredentialsError::NoCreds));
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_profile_uses_default_section() -> Result<()> {
+        clear_profile_env();
+
+        let file = tempfile::NamedTempFile::new()?;
+        std::fs::write(
+            file.path(),
+            "# a comment\n[default]\napplication_key_id = id\napplication_key = key\n\n[other]\napplication_key_id = other_id\napplication_key = other_key\n",
+        )?;
+
+        let creds = Credentials::from_profile(Some(file.path()), None)?;
+        assert_eq!(creds.application_key_id, "id");
+        assert_eq!(creds.application_key, "key");
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_profile_uses_named_profile() -> Result<()> {
+        clear_profile_env();
+
+        let file = tempfile::NamedTempFile::new()?;
+        std::fs::write(
+            file.path(),
+            "[default]\napplication_key_id = id\napplication_key = key\n\n[work]\napplication_key_id = work_id\napplication_key = work_key\n",
+        )?;
+
+        let creds = Credentials::from_profile(Some(file.path()), Some("work"))?;
+        assert_eq!(creds.application_key_id, "work_id");
+        assert_eq!(creds.application_key, "work_key");
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_profile_tolerates_quotes_and_whitespace() -> Result<()> {
+        clear_profile_env();
+
+        let file = tempfile::NamedTempFile::new()?;
+        std::fs::write(
+            file.path(),
+            "  [default]  \n  application_key_id   =   \"id\"  \n  application_key = 'key'  ; trailing comment line above\n",
+        )?;
+
+        let creds = Credentials::from_profile(Some(file.path()), None)?;
+        assert_eq!(creds.application_key_id, "id");
+        assert_eq!(creds.application_key, "key");
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_profile_uses_env_var_for_profile_name() -> Result<()> {
+        clear_profile_env();
+
+        let file = tempfile::NamedTempFile::new()?;
+        std::fs::write(
+            file.path(),
+            "[work]\napplication_key_id = work_id\napplication_key = work_key\n",
+        )?;
+
+        std::env::set_var(PROFILE_ENV_VAR_NAME, "work");
+        let creds = Credentials::from_profile(Some(file.path()), None);
+        std::env::remove_var(PROFILE_ENV_VAR_NAME);
+
+        let creds = creds?;
+        assert_eq!(creds.application_key_id, "work_id");
+        assert_eq!(creds.application_key, "work_key");
+
+        Ok(())
+    }
 }
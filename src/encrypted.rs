@@ -0,0 +1,244 @@
+//! A self-describing, optionally-encrypted value used for the
+//! `application_key` column of the credentials database.
+//!
+//! Values are stored either as plain UTF-8 (matching the layout the B2 CLI
+//! itself uses) or, when a passphrase is supplied, as a binary blob of the
+//! form `salt || u64-LE len(mac) || mac || u64-LE len(iv) || iv ||
+//! u64-LE len(ciphertext) || ciphertext`. [`FromSql`] tells the two apart by
+//! trying to parse the binary layout first and falling back to plain UTF-8 if
+//! that fails, so existing unencrypted `.b2_account_info` files keep working.
+
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+
+use aes::cipher::{KeyIvInit, StreamCipher};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use std::convert::TryInto;
+
+use crate::CredentialsError;
+
+type Result<T> = std::result::Result<T, CredentialsError>;
+type Aes256Ctr = ctr::Ctr64BE<aes::Aes256>;
+type HmacSha256 = Hmac<Sha256>;
+
+const SALT_LEN: usize = 16;
+const IV_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum EncryptedValue {
+    Plain(String),
+    Encrypted {
+        salt: Vec<u8>,
+        mac: Vec<u8>,
+        iv: Vec<u8>,
+        ciphertext: Vec<u8>,
+    },
+}
+
+impl EncryptedValue {
+    pub(crate) fn plain(value: &str) -> Self {
+        Self::Plain(value.to_string())
+    }
+
+    pub(crate) fn encrypt(value: &str, passphrase: &str) -> Result<Self> {
+        let mut salt = vec![0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let mut iv = vec![0u8; IV_LEN];
+        rand::thread_rng().fill_bytes(&mut iv);
+
+        let key = derive_key(passphrase, &salt)?;
+
+        let mut ciphertext = value.as_bytes().to_vec();
+        let mut cipher =
+            Aes256Ctr::new_from_slices(&key, &iv).map_err(|_| CredentialsError::Decrypt)?;
+        cipher.apply_keystream(&mut ciphertext);
+
+        let mac = compute_mac(&key, &iv, &ciphertext);
+
+        Ok(Self::Encrypted {
+            salt,
+            mac,
+            iv,
+            ciphertext,
+        })
+    }
+
+    /// Returns the plaintext application key, decrypting it first if it was
+    /// stored encrypted. A missing or incorrect `passphrase` for an encrypted
+    /// value results in `CredentialsError::Decrypt`.
+    pub(crate) fn reveal(&self, passphrase: Option<&str>) -> Result<String> {
+        match self {
+            Self::Plain(value) => Ok(value.clone()),
+            Self::Encrypted {
+                salt,
+                mac,
+                iv,
+                ciphertext,
+            } => {
+                let passphrase = passphrase.ok_or(CredentialsError::Decrypt)?;
+                let key = derive_key(passphrase, salt)?;
+
+                verify_mac(&key, iv, ciphertext, mac)?;
+
+                let mut plaintext = ciphertext.clone();
+                let mut cipher = Aes256Ctr::new_from_slices(&key, iv)
+                    .map_err(|_| CredentialsError::Decrypt)?;
+                cipher.apply_keystream(&mut plaintext);
+
+                String::from_utf8(plaintext).map_err(|_| CredentialsError::Decrypt)
+            }
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Plain(value) => value.as_bytes().to_vec(),
+            Self::Encrypted {
+                salt,
+                mac,
+                iv,
+                ciphertext,
+            } => {
+                let mut bytes = Vec::with_capacity(
+                    salt.len() + 8 + mac.len() + 8 + iv.len() + 8 + ciphertext.len(),
+                );
+                bytes.extend_from_slice(salt);
+                write_len_prefixed(&mut bytes, mac);
+                write_len_prefixed(&mut bytes, iv);
+                write_len_prefixed(&mut bytes, ciphertext);
+                bytes
+            }
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self::try_parse_encrypted(bytes)
+            .unwrap_or_else(|| Self::Plain(String::from_utf8_lossy(bytes).into_owned()))
+    }
+
+    fn try_parse_encrypted(bytes: &[u8]) -> Option<Self> {
+        let salt = bytes.get(..SALT_LEN)?.to_vec();
+        let offset = SALT_LEN;
+
+        let (mac, offset) = read_len_prefixed(bytes, offset)?;
+        let (iv, offset) = read_len_prefixed(bytes, offset)?;
+        let (ciphertext, offset) = read_len_prefixed(bytes, offset)?;
+
+        if offset != bytes.len() || iv.len() != IV_LEN {
+            return None;
+        }
+
+        Some(Self::Encrypted {
+            salt,
+            mac,
+            iv,
+            ciphertext,
+        })
+    }
+}
+
+fn write_len_prefixed(bytes: &mut Vec<u8>, data: &[u8]) {
+    bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(data);
+}
+
+fn read_len_prefixed(bytes: &[u8], offset: usize) -> Option<(Vec<u8>, usize)> {
+    let len = u64::from_le_bytes(bytes.get(offset..offset + 8)?.try_into().ok()?) as usize;
+    let start = offset + 8;
+    let data = bytes.get(start..start + len)?.to_vec();
+    Some((data, start + len))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Vec<u8>> {
+    let params = scrypt::Params::new(15, 8, 1, KEY_LEN).map_err(|_| CredentialsError::Decrypt)?;
+    let mut key = vec![0u8; KEY_LEN];
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+        .map_err(|_| CredentialsError::Decrypt)?;
+    Ok(key)
+}
+
+fn compute_mac(key: &[u8], iv: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(iv);
+    mac.update(ciphertext);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Verifies `mac` against `iv`/`ciphertext` in constant time, so a mismatch
+/// can't be used as a timing oracle to recover the expected tag byte by byte.
+fn verify_mac(key: &[u8], iv: &[u8], ciphertext: &[u8], mac: &[u8]) -> Result<()> {
+    let mut expected = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    expected.update(iv);
+    expected.update(ciphertext);
+    expected.verify_slice(mac).map_err(|_| CredentialsError::Decrypt)
+}
+
+impl ToSql for EncryptedValue {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        match self {
+            Self::Plain(value) => Ok(ToSqlOutput::from(value.as_str())),
+            Self::Encrypted { .. } => Ok(ToSqlOutput::from(self.to_bytes())),
+        }
+    }
+}
+
+impl FromSql for EncryptedValue {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let bytes = match value {
+            ValueRef::Text(bytes) | ValueRef::Blob(bytes) => bytes,
+            _ => return Err(FromSqlError::InvalidType),
+        };
+        Ok(Self::from_bytes(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_round_trips_without_passphrase() {
+        let value = EncryptedValue::plain("my-key");
+        assert_eq!(value.reveal(None).unwrap(), "my-key");
+    }
+
+    #[test]
+    fn encrypted_round_trips_with_correct_passphrase() {
+        let value = EncryptedValue::encrypt("my-key", "hunter2").unwrap();
+        assert_eq!(value.reveal(Some("hunter2")).unwrap(), "my-key");
+    }
+
+    #[test]
+    fn encrypted_fails_with_no_passphrase() {
+        let value = EncryptedValue::encrypt("my-key", "hunter2").unwrap();
+        assert!(matches!(
+            value.reveal(None).unwrap_err(),
+            CredentialsError::Decrypt
+        ));
+    }
+
+    #[test]
+    fn encrypted_fails_with_wrong_passphrase() {
+        let value = EncryptedValue::encrypt("my-key", "hunter2").unwrap();
+        assert!(matches!(
+            value.reveal(Some("wrong")).unwrap_err(),
+            CredentialsError::Decrypt
+        ));
+    }
+
+    #[test]
+    fn bytes_round_trip_through_encrypted_layout() {
+        let value = EncryptedValue::encrypt("my-key", "hunter2").unwrap();
+        let bytes = value.to_bytes();
+        let parsed = EncryptedValue::from_bytes(&bytes);
+        assert_eq!(parsed, value);
+    }
+
+    #[test]
+    fn arbitrary_bytes_fall_back_to_plain() {
+        let parsed = EncryptedValue::from_bytes(b"just-a-plaintext-key");
+        assert_eq!(parsed, EncryptedValue::Plain("just-a-plaintext-key".to_string()));
+    }
+}
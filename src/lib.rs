@@ -4,14 +4,20 @@
 //! used by the B2 CLI.
 //!
 //! ```no_run
-//! let creds = b2creds::Credentials::locate().unwrap();
+//! let creds = b2creds::Credentials::default().unwrap();
 //! println!("Key ID: {} Key: {}", creds.application_key_id, creds.application_key);
 //!```
 //!
-//! Look at the [`Credentials::locate`], [`Credentials::from_env`]. and
-//! [`Credentials::from_file`] to understand how to parse B2 credentials.
+//! Look at [`Credentials::default`], [`Credentials::from_env`],
+//! [`Credentials::from_systemd_credentials`], [`Credentials::from_file`],
+//! [`Credentials::from_file_with_passphrase`],
+//! [`Credentials::from_file_with_opts`], and [`Credentials::from_profile`] to
+//! understand how to parse B2 credentials, [`Credentials::list_accounts`] to
+//! enumerate the accounts in a credentials database, and [`Credentials::save`]
+//! to persist credentials to one.
 
 mod credentials;
+mod encrypted;
 pub use credentials::*;
 
 #[cfg(test)]